@@ -0,0 +1,169 @@
+//! Streaming artifact log of every `ChainEvent` emitted during a commit run.
+//!
+//! When `--event-log` is set, each event is appended to the file as a JSON
+//! line the instant it arrives — in both the actor-start and
+//! request-message loops — so a long-running or timed-out commit still
+//! leaves a complete, replayable trace instead of just the transient
+//! emoji lines `handle_commit_event` prints.
+//!
+//! The writer only ever emits JSONL: there's deliberately no
+//! `--event-log-format` flag on the top-level command to pick a write-time
+//! format. A "pretty" writer wouldn't be replayable, which defeats the
+//! point of the log, so the format choice lives solely on `th-commit
+//! replay` (see `EventLogFormat` below). Don't reintroduce a write-side
+//! format flag thinking its absence is an oversight.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use theater::ChainEvent;
+
+/// Rendering format for `th-commit replay`.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum EventLogFormat {
+    Jsonl,
+    Pretty,
+}
+
+/// One recorded event, as written to the event log.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    pub event_type: String,
+    pub description: Option<String>,
+    pub data_base64: String,
+    pub timestamp: String,
+}
+
+impl LoggedEvent {
+    fn from_chain_event(event: &ChainEvent) -> Self {
+        Self {
+            event_type: event.event_type.clone(),
+            description: event.description.clone(),
+            data_base64: base64::engine::general_purpose::STANDARD.encode(&event.data),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Open handle that appends `ChainEvent`s to the log file as they arrive.
+pub struct EventLog {
+    file: File,
+}
+
+impl EventLog {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event log at {}", path.display()))?;
+        Ok(Self { file })
+    }
+
+    /// Append one event, flushing immediately so a run that times out or
+    /// crashes still leaves a complete trace on disk.
+    pub fn append(&mut self, event: &ChainEvent) -> Result<()> {
+        let logged = LoggedEvent::from_chain_event(event);
+        let line = serde_json::to_string(&logged)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Read back a previously written event log and re-render it through the
+/// same UI helpers used during a live run.
+pub fn replay(path: &Path, format: EventLogFormat) -> Result<()> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open event log at {}", path.display()))?;
+    let reader = BufReader::new(file);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let logged: LoggedEvent = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse event log line: {}", line))?;
+        render_logged_event(&logged, format);
+    }
+
+    Ok(())
+}
+
+fn render_logged_event(event: &LoggedEvent, format: EventLogFormat) {
+    match format {
+        EventLogFormat::Jsonl => {
+            if let Ok(line) = serde_json::to_string(event) {
+                println!("{}", line);
+            }
+        }
+        EventLogFormat::Pretty => {
+            crate::ui::print_item("Event", &event.event_type, Some("info"));
+            if let Some(desc) = &event.description {
+                crate::ui::print_item("Description", desc, Some("dim"));
+            }
+            crate::ui::print_item("Recorded at", &event.timestamp, Some("dim"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_FILE_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    fn temp_log_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "th-commit-test-eventlog-{}-{}.jsonl",
+            std::process::id(),
+            TEST_FILE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ))
+    }
+
+    fn sample_event(event_type: &str, description: Option<&str>) -> ChainEvent {
+        ChainEvent {
+            event_type: event_type.to_string(),
+            description: description.map(str::to_string),
+            data: b"payload".to_vec(),
+        }
+    }
+
+    #[test]
+    fn append_then_replay_round_trips_through_jsonl() {
+        let path = temp_log_path();
+        {
+            let mut log = EventLog::open(&path).unwrap();
+            log.append(&sample_event("git_status_check", Some("checking status")))
+                .unwrap();
+            log.append(&sample_event("creating_commit", None)).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: LoggedEvent = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.event_type, "git_status_check");
+        assert_eq!(first.description.as_deref(), Some("checking status"));
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD
+                .decode(&first.data_base64)
+                .unwrap(),
+            b"payload"
+        );
+
+        let second: LoggedEvent = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second.event_type, "creating_commit");
+        assert_eq!(second.description, None);
+
+        assert!(replay(&path, EventLogFormat::Jsonl).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
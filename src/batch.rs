@@ -0,0 +1,260 @@
+//! Multi-repo batch commit driver.
+//!
+//! `th-commit batch --repos <glob-or-file>` generalizes the single-repo
+//! flow in `execute_commit` to a whole workspace: each matched repository
+//! gets its own commit actor over its own `EventDrivenClient`, bounded by a
+//! `--jobs` worker pool, with `--timeout-seconds` applied per repo rather
+//! than to the batch as a whole. A failure in one repo is recorded and
+//! does not stop the rest from running.
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::{
+    build_commit_record, persist_commit_record, request_commit_result, start_commit_actor,
+    stop_commit_actor, ui, validate_prerequisites, Args, EventDrivenClient,
+};
+
+/// Resolve `--repos` into a list of repository directories, either by
+/// reading it as a newline-delimited file or by treating it as a glob.
+fn collect_repo_paths(spec: &str) -> Result<Vec<PathBuf>> {
+    let as_path = std::path::Path::new(spec);
+    if as_path.is_file() {
+        let contents = std::fs::read_to_string(as_path)
+            .with_context(|| format!("Failed to read repo list at {}", spec))?;
+        return Ok(contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(PathBuf::from)
+            .collect());
+    }
+
+    let mut paths = Vec::new();
+    for entry in glob::glob(spec).with_context(|| format!("Invalid repo glob: {}", spec))? {
+        let path = entry.with_context(|| format!("Failed to read glob entry for {}", spec))?;
+        if path.is_dir() {
+            paths.push(path);
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("No repositories matched {}", spec));
+    }
+
+    Ok(paths)
+}
+
+/// Commit the current state of a single repository and return the
+/// resulting journal entry.
+async fn run_repo(args: Args, repo_path: PathBuf, api_key: String) -> Result<crate::state::CommitRecord> {
+    validate_prerequisites(&repo_path)?;
+
+    let mut client = EventDrivenClient::new(&args.server, None)
+        .await
+        .context("Failed to connect to Theater server")?;
+
+    let initial_state = json!({
+        "repository_path": repo_path.to_string_lossy(),
+        "api_key": api_key,
+        "auto_push": args.auto_push,
+        "message_prefix": args.prefix,
+        "skip_staging": args.skip_staging,
+        "dry_run": args.dry_run
+    });
+
+    let actor_id = start_commit_actor(&mut client, initial_state).await?;
+
+    let commit_request = json!({
+        "action": "commit",
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+
+    let result =
+        request_commit_result(&mut client, &actor_id, commit_request, args.timeout_seconds)
+            .await?;
+
+    stop_commit_actor(&mut client, &actor_id).await;
+
+    // A journal-write failure (e.g. SQLITE_BUSY from two workers finishing
+    // at once) must not be reported as a commit failure for this repo — the
+    // actual git commit already succeeded.
+    let record = build_commit_record(&repo_path, &actor_id, &result);
+    if let Err(e) = persist_commit_record(&record) {
+        eprintln!(
+            "Warning: Failed to record commit journal entry for {}: {}",
+            repo_path.display(),
+            e
+        );
+    }
+    Ok(record)
+}
+
+/// Run `th-commit batch`: commit every matched repo, bounded by `jobs`
+/// concurrent workers, and print a summary table.
+pub async fn run(args: &Args, repos_spec: &str, jobs: usize) -> Result<()> {
+    let repo_paths = collect_repo_paths(repos_spec)?;
+    let api_key = std::env::var("GOOGLE_GEMINI_API_KEY")
+        .context("GOOGLE_GEMINI_API_KEY environment variable not set")?;
+
+    ui::print_header();
+    ui::print_section("Batch Commit");
+    ui::print_item("Repositories", &repo_paths.len().to_string(), Some("highlight"));
+    ui::print_item("Concurrency", &jobs.to_string(), Some("info"));
+
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    let mut tasks = Vec::with_capacity(repo_paths.len());
+
+    for repo_path in repo_paths {
+        let semaphore = semaphore.clone();
+        let args = args.clone();
+        let api_key = api_key.clone();
+        let task_repo_path = repo_path.clone();
+        tasks.push((
+            repo_path,
+            tokio::spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("batch semaphore should never be closed");
+                run_repo(args, task_repo_path, api_key).await
+            }),
+        ));
+    }
+
+    let failed_record = |repo_path: &std::path::Path, error: String| crate::state::CommitRecord {
+        actor_id: String::new(),
+        repo_path: repo_path.to_string_lossy().to_string(),
+        commit_hash: None,
+        commit_message: None,
+        files_changed: None,
+        insertions: None,
+        deletions: None,
+        pushed: false,
+        success: false,
+        error: Some(error),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let mut records = Vec::with_capacity(tasks.len());
+    for (repo_path, task) in tasks {
+        match task.await {
+            Ok(Ok(record)) => records.push(record),
+            Ok(Err(e)) => {
+                eprintln!("Warning: commit failed for {}: {}", repo_path.display(), e);
+                records.push(failed_record(&repo_path, e.to_string()));
+            }
+            Err(e) => {
+                eprintln!("Warning: batch worker panicked for {}: {}", repo_path.display(), e);
+                records.push(failed_record(&repo_path, format!("worker panicked: {}", e)));
+            }
+        }
+    }
+
+    render_summary(&records);
+
+    let failures = records.iter().filter(|r| !r.success).count();
+    if failures > 0 {
+        ui::print_item(
+            "Result",
+            &format!("{} of {} repos failed", failures, records.len()),
+            Some("warning"),
+        );
+    } else {
+        ui::print_item("Result", "All repos committed successfully", Some("success"));
+    }
+
+    Ok(())
+}
+
+fn render_summary(records: &[crate::state::CommitRecord]) {
+    ui::print_section("Summary");
+
+    let headers = ["Repo", "Status", "Hash", "Files", "Ins", "Dels", "Pushed"];
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| {
+            vec![
+                r.repo_path.clone(),
+                if r.success { "success".to_string() } else { "failed".to_string() },
+                r.commit_hash.clone().unwrap_or_else(|| "-".to_string()),
+                r.files_changed.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+                r.insertions.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+                r.deletions.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+                if r.pushed { "yes".to_string() } else { "no".to_string() },
+            ]
+        })
+        .collect();
+
+    ui::print_table(&headers, &rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_FILE_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A throwaway repo-list file, removed when the guard drops.
+    struct TempRepoList {
+        path: PathBuf,
+    }
+
+    impl TempRepoList {
+        fn new(contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "th-commit-test-repos-{}-{}.txt",
+                std::process::id(),
+                TEST_FILE_COUNTER.fetch_add(1, Ordering::SeqCst)
+            ));
+            std::fs::write(&path, contents).expect("failed to write temp repo list");
+            Self { path }
+        }
+    }
+
+    impl Drop for TempRepoList {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn collect_repo_paths_reads_file_skipping_comments_and_blanks() {
+        let file = TempRepoList::new("/repo/one\n# a comment\n\n  /repo/two  \n");
+        let paths = collect_repo_paths(file.path.to_str().unwrap()).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("/repo/one"), PathBuf::from("/repo/two")]);
+    }
+
+    #[test]
+    fn collect_repo_paths_treats_non_file_spec_as_glob() {
+        let dir = std::env::temp_dir().join(format!(
+            "th-commit-test-glob-{}-{}",
+            std::process::id(),
+            TEST_FILE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(dir.join("repo-a")).unwrap();
+        std::fs::create_dir_all(dir.join("repo-b")).unwrap();
+        std::fs::write(dir.join("not-a-repo.txt"), "").unwrap();
+
+        let spec = dir.join("repo-*").to_string_lossy().to_string();
+        let mut paths = collect_repo_paths(&spec).unwrap();
+        paths.sort();
+
+        assert_eq!(paths, vec![dir.join("repo-a"), dir.join("repo-b")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn collect_repo_paths_errors_when_glob_matches_nothing() {
+        let spec = std::env::temp_dir()
+            .join("th-commit-test-no-such-glob-*")
+            .to_string_lossy()
+            .to_string();
+        assert!(collect_repo_paths(&spec).is_err());
+    }
+}
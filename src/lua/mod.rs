@@ -0,0 +1,154 @@
+//! Embedded Lua runtime for user-defined commit hooks.
+//!
+//! A script passed via `--script` may define a `pre_commit(state)` function
+//! that can mutate the actor's initial state (currently just
+//! `message_prefix`) or abort the commit, and a `post_commit(result)`
+//! function that can accept, rewrite, or reject (triggering a regenerate)
+//! the message the actor produced. The runtime is loaded with only the
+//! safe standard libraries minus `os` and `io`, so scripts get no
+//! filesystem or process access beyond the callbacks we register below.
+
+use anyhow::{Context, Result};
+use mlua::{Lua, LuaOptions, LuaSerdeExt, StdLib, Value};
+
+use crate::CommitResult;
+
+/// Outcome of running the `pre_commit` hook.
+pub struct PreCommitOutcome {
+    pub message_prefix: Option<String>,
+    pub abort: bool,
+}
+
+/// Outcome of running the `post_commit` hook.
+pub enum PostCommitOutcome {
+    Accept,
+    Rewrite(String),
+    Regenerate,
+}
+
+/// A loaded user script and the sandboxed runtime it executes in.
+pub struct Script {
+    lua: Lua,
+}
+
+impl Script {
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}", path.display()))?;
+
+        let sandboxed_libs = StdLib::ALL_SAFE - StdLib::OS - StdLib::IO;
+        let lua = Lua::new_with(sandboxed_libs, LuaOptions::default())
+            .context("Failed to initialize sandboxed Lua runtime")?;
+        register_callbacks(&lua)?;
+
+        lua.load(&source)
+            .exec()
+            .with_context(|| format!("Failed to load script {}", path.display()))?;
+
+        Ok(Self { lua })
+    }
+
+    /// Call the user's `pre_commit(state)` function, if defined.
+    pub fn run_pre_commit(&self, initial_state: &serde_json::Value) -> Result<PreCommitOutcome> {
+        let globals = self.lua.globals();
+        let Ok(func) = globals.get::<_, mlua::Function>("pre_commit") else {
+            return Ok(PreCommitOutcome {
+                message_prefix: None,
+                abort: false,
+            });
+        };
+
+        let state_value = self
+            .lua
+            .to_value(initial_state)
+            .context("Failed to convert initial state for Lua")?;
+
+        let outcome: Value = func
+            .call(state_value)
+            .context("pre_commit hook raised an error")?;
+
+        Ok(match outcome {
+            Value::Table(table) => PreCommitOutcome {
+                message_prefix: table.get("message_prefix").ok(),
+                abort: table.get("abort").unwrap_or(false),
+            },
+            _ => PreCommitOutcome {
+                message_prefix: None,
+                abort: false,
+            },
+        })
+    }
+
+    /// Call the user's `post_commit(result)` function, if defined.
+    pub fn run_post_commit(&self, result: &CommitResult) -> Result<PostCommitOutcome> {
+        let globals = self.lua.globals();
+        let Ok(func) = globals.get::<_, mlua::Function>("post_commit") else {
+            return Ok(PostCommitOutcome::Accept);
+        };
+
+        let result_value = self
+            .lua
+            .to_value(result)
+            .context("Failed to convert commit result for Lua")?;
+
+        let outcome: Value = func
+            .call(result_value)
+            .context("post_commit hook raised an error")?;
+
+        let Value::Table(table) = outcome else {
+            return Ok(PostCommitOutcome::Accept);
+        };
+
+        let action: String = table
+            .get("action")
+            .unwrap_or_else(|_| "accept".to_string());
+
+        match action.as_str() {
+            "reject" | "regenerate" => Ok(PostCommitOutcome::Regenerate),
+            "rewrite" => {
+                let message: String = table
+                    .get("message")
+                    .context("post_commit returned action=\"rewrite\" without a message")?;
+                Ok(PostCommitOutcome::Rewrite(message))
+            }
+            _ => Ok(PostCommitOutcome::Accept),
+        }
+    }
+}
+
+/// Expose a handful of safe Rust callbacks scripts can call into, routed
+/// through the existing `ui` helpers instead of raw stdout.
+fn register_callbacks(lua: &Lua) -> Result<()> {
+    let globals = lua.globals();
+
+    let log_info = lua.create_function(|_, message: String| {
+        crate::ui::print_item("Script", &message, Some("info"));
+        Ok(())
+    })?;
+    globals.set("log_info", log_info)?;
+
+    let log_warning = lua.create_function(|_, message: String| {
+        crate::ui::print_item("Script", &message, Some("warning"));
+        Ok(())
+    })?;
+    globals.set("log_warning", log_warning)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandboxed_runtime_has_no_os_or_io_access() {
+        let sandboxed_libs = StdLib::ALL_SAFE - StdLib::OS - StdLib::IO;
+        let lua = Lua::new_with(sandboxed_libs, LuaOptions::default()).unwrap();
+
+        let os_is_nil: bool = lua.load("return os == nil").eval().unwrap();
+        assert!(os_is_nil, "`os` should not be reachable from a script");
+
+        let io_is_nil: bool = lua.load("return io == nil").eval().unwrap();
+        assert!(io_is_nil, "`io` should not be reachable from a script");
+    }
+}
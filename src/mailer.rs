@@ -0,0 +1,298 @@
+//! Email the freshly created commit as a patch to reviewers.
+//!
+//! Gated behind `--mail-to`, this runs `git format-patch -1 <hash>` on the
+//! commit the actor just created and delivers the resulting patch as an
+//! RFC822 message, either through a local `sendmail` binary or SMTP.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Recipients and sender for a patch email.
+pub struct MailConfig {
+    pub to: Vec<String>,
+    pub from: String,
+}
+
+impl MailConfig {
+    /// Build a `MailConfig`, stripping any CR/LF from `to`/`from` the same
+    /// way the subject line already is, so a `--mail-to`/`--mail-from`
+    /// value can't inject extra RFC822 headers or SMTP commands.
+    pub fn new(to: Vec<String>, from: String) -> Self {
+        Self {
+            to: to.iter().map(|addr| strip_crlf(addr)).collect(),
+            from: strip_crlf(&from),
+        }
+    }
+}
+
+fn strip_crlf(value: &str) -> String {
+    value.replace(['\r', '\n'], "")
+}
+
+/// Run `git format-patch` for `commit_hash` and email the result.
+pub fn send_patch_mail(
+    repo_path: &Path,
+    commit_hash: &str,
+    commit_message: &str,
+    config: &MailConfig,
+) -> Result<()> {
+    let patch = format_patch(repo_path, commit_hash)?;
+    let subject = commit_message
+        .lines()
+        .next()
+        .unwrap_or("New commit")
+        .replace('\r', "");
+    let message = build_message(config, &subject, &patch);
+    deliver(&message, config)
+}
+
+fn format_patch(repo_path: &Path, commit_hash: &str) -> Result<String> {
+    let output = Command::new("git")
+        .arg("format-patch")
+        .arg("-1")
+        .arg(commit_hash)
+        .arg("--stdout")
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git format-patch")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git format-patch failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn build_message(config: &MailConfig, subject: &str, patch: &str) -> String {
+    format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+        config.from,
+        config.to.join(", "),
+        subject,
+        patch
+    )
+}
+
+fn deliver(message: &str, config: &MailConfig) -> Result<()> {
+    match deliver_via_sendmail(message, config) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            eprintln!("Warning: sendmail delivery failed ({}), falling back to SMTP", e);
+            deliver_via_smtp(message, config)
+        }
+    }
+}
+
+fn deliver_via_sendmail(message: &str, config: &MailConfig) -> Result<()> {
+    let mut child = Command::new("sendmail")
+        .arg("-t")
+        .arg("-i")
+        .arg("--")
+        .args(&config.to)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("sendmail binary not available")?;
+
+    child
+        .stdin
+        .take()
+        .context("Failed to open sendmail stdin")?
+        .write_all(message.as_bytes())
+        .context("Failed to write patch email to sendmail")?;
+
+    let status = child.wait().context("Failed to wait on sendmail")?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("sendmail exited with status {}", status));
+    }
+
+    Ok(())
+}
+
+/// Read an SMTP reply and fail unless its status code is 2xx/3xx, so a
+/// rejected `RCPT TO`/`MAIL FROM` (or any other non-success reply) surfaces
+/// as a delivery error instead of being silently treated as success.
+///
+/// Replies may span multiple lines (e.g. a multi-line `EHLO` response); per
+/// RFC 5321 every line but the last has a `-` as the 4th byte, so we keep
+/// reading until we see a line with a space there, and only validate that
+/// final line's code. Leaving intermediate lines unread would shift every
+/// subsequent `expect_smtp_reply` call onto the wrong reply.
+fn expect_smtp_reply(reader: &mut impl std::io::BufRead, stage: &str) -> Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let bytes_read = reader
+            .read_line(&mut line)
+            .with_context(|| format!("Failed to read SMTP reply after {}", stage))?;
+
+        if bytes_read == 0 {
+            return Err(anyhow::anyhow!(
+                "Connection closed while reading SMTP reply after {}",
+                stage
+            ));
+        }
+
+        if line.as_bytes().get(3).is_some_and(|&b| b != b'-') {
+            break;
+        }
+    }
+
+    let code: u16 = line
+        .get(0..3)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("Malformed SMTP reply after {}: {:?}", stage, line))?;
+
+    if !(200..400).contains(&code) {
+        return Err(anyhow::anyhow!(
+            "SMTP server rejected {} ({}): {}",
+            stage,
+            code,
+            line.trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Escape lines that start with `.` per RFC 5321 dot-stuffing, so a patch
+/// body containing a line of just `.` doesn't truncate the `DATA` section.
+///
+/// Expects `message` to already be normalized to CRLF line endings (see
+/// `normalize_to_crlf`) so the lines this splits on match what the wire
+/// and a receiving MTA agree are "lines".
+fn dot_stuff(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    for (i, line) in message.split("\r\n").enumerate() {
+        if i > 0 {
+            result.push_str("\r\n");
+        }
+        if line.starts_with('.') {
+            result.push('.');
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+/// Normalize every line ending in `message` to CRLF.
+///
+/// `build_message`'s headers are already CRLF-terminated, but the patch
+/// body comes from `git format-patch`, which emits bare LF. Mixing the two
+/// lets a sender and a strict/receiving MTA disagree about where lines
+/// (and therefore the dot-stuffed escape and the `DATA` terminator) fall —
+/// the ambiguity behind the 2023 "SMTP smuggling" class of bugs. Normalize
+/// the whole message up front so dot-stuffing and the wire format agree.
+fn normalize_to_crlf(message: &str) -> String {
+    message.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+fn deliver_via_smtp(message: &str, config: &MailConfig) -> Result<()> {
+    use std::io::BufReader;
+    use std::net::TcpStream;
+
+    let host = std::env::var("SMTP_HOST").context("SMTP_HOST not set (no sendmail available)")?;
+    let port: u16 = std::env::var("SMTP_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(25);
+
+    let stream = TcpStream::connect((host.as_str(), port))
+        .with_context(|| format!("Failed to connect to SMTP server {}:{}", host, port))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    expect_smtp_reply(&mut reader, "greeting")?;
+
+    writer.write_all(b"EHLO localhost\r\n")?;
+    expect_smtp_reply(&mut reader, "EHLO")?;
+
+    writer.write_all(format!("MAIL FROM:<{}>\r\n", config.from).as_bytes())?;
+    expect_smtp_reply(&mut reader, "MAIL FROM")?;
+
+    for recipient in &config.to {
+        writer.write_all(format!("RCPT TO:<{}>\r\n", recipient).as_bytes())?;
+        expect_smtp_reply(&mut reader, "RCPT TO")?;
+    }
+
+    writer.write_all(b"DATA\r\n")?;
+    expect_smtp_reply(&mut reader, "DATA")?;
+
+    let message = normalize_to_crlf(message);
+    writer.write_all(dot_stuff(&message).as_bytes())?;
+    writer.write_all(b"\r\n.\r\n")?;
+    expect_smtp_reply(&mut reader, "end of DATA")?;
+
+    writer.write_all(b"QUIT\r\n")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mail_config_strips_crlf_from_recipients_and_sender() {
+        let config = MailConfig::new(
+            vec!["a@example.com\r\nBcc: evil@example.com".to_string()],
+            "me@example.com\r\nX-Injected: true".to_string(),
+        );
+        assert_eq!(config.to, vec!["a@example.comBcc: evil@example.com"]);
+        assert_eq!(config.from, "me@example.comX-Injected: true");
+    }
+
+    #[test]
+    fn dot_stuff_escapes_leading_dots() {
+        assert_eq!(dot_stuff("hello\r\n.\r\nworld"), "hello\r\n..\r\nworld");
+        assert_eq!(dot_stuff(".leading"), "..leading");
+        assert_eq!(dot_stuff("no dots here"), "no dots here");
+    }
+
+    #[test]
+    fn normalize_to_crlf_converts_bare_lf_and_leaves_crlf_alone() {
+        assert_eq!(normalize_to_crlf("a\nb\r\nc"), "a\r\nb\r\nc");
+        assert_eq!(normalize_to_crlf("already\r\ncrlf\r\n"), "already\r\ncrlf\r\n");
+    }
+
+    #[test]
+    fn expect_smtp_reply_accepts_2xx_and_3xx() {
+        let mut reader = std::io::Cursor::new(b"250 OK\r\n".to_vec());
+        assert!(expect_smtp_reply(&mut reader, "test").is_ok());
+
+        let mut reader = std::io::Cursor::new(b"354 Start mail input\r\n".to_vec());
+        assert!(expect_smtp_reply(&mut reader, "test").is_ok());
+    }
+
+    #[test]
+    fn expect_smtp_reply_rejects_error_codes() {
+        let mut reader = std::io::Cursor::new(b"550 no such user\r\n".to_vec());
+        assert!(expect_smtp_reply(&mut reader, "RCPT TO").is_err());
+    }
+
+    #[test]
+    fn expect_smtp_reply_rejects_malformed_lines() {
+        let mut reader = std::io::Cursor::new(b"not a reply\r\n".to_vec());
+        assert!(expect_smtp_reply(&mut reader, "test").is_err());
+    }
+
+    #[test]
+    fn expect_smtp_reply_consumes_multiline_replies() {
+        let mut reader = std::io::Cursor::new(
+            b"250-mail.example.com Hello\r\n250-SIZE 10240000\r\n250 HELP\r\n".to_vec(),
+        );
+        assert!(expect_smtp_reply(&mut reader, "EHLO").is_ok());
+
+        // The whole multi-line reply was consumed, so the next read sees the
+        // following command's reply rather than a leftover EHLO line.
+        let mut reader = std::io::Cursor::new(
+            b"250-mail.example.com Hello\r\n250 HELP\r\n550 no such user\r\n".to_vec(),
+        );
+        assert!(expect_smtp_reply(&mut reader, "EHLO").is_ok());
+        assert!(expect_smtp_reply(&mut reader, "RCPT TO").is_err());
+    }
+}
@@ -2,7 +2,7 @@
 //! This version handles all Theater messages asynchronously without relying on message ordering
 
 use anyhow::{anyhow, Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::env;
@@ -15,11 +15,21 @@ use theater_server::{ManagementCommand, ManagementResponse};
 use tokio::time::timeout;
 use tracing::info;
 
+mod artifact;
+mod batch;
+mod lua;
+mod mailer;
+mod notifier;
+mod serve;
+mod state;
 mod ui;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "th-commit", about = "AI-powered git commits using Theater")]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Theater server address
     #[arg(long, env = "THEATER_SERVER_ADDRESS", default_value = "127.0.0.1:9000")]
     server: String,
@@ -47,6 +57,81 @@ struct Args {
     /// Enable verbose logging
     #[arg(long, short)]
     verbose: bool,
+
+    /// Path to a TOML file configuring notification backends
+    #[arg(long)]
+    notify_config: Option<std::path::PathBuf>,
+
+    /// Email the new commit as a patch to these recipients (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    mail_to: Option<Vec<String>>,
+
+    /// From address used when mailing commit patches
+    #[arg(long, env = "TH_COMMIT_MAIL_FROM")]
+    mail_from: Option<String>,
+
+    /// Lua script defining pre_commit/post_commit hooks
+    #[arg(long)]
+    script: Option<std::path::PathBuf>,
+
+    /// Append every actor ChainEvent to this file as it arrives, as JSONL.
+    /// There's no format option here by design — see artifact.rs.
+    #[arg(long)]
+    event_log: Option<std::path::PathBuf>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Commands {
+    /// Show the history of commit operations recorded in the local journal
+    Log {
+        /// Only show history for this repository path
+        #[arg(long)]
+        repo: Option<std::path::PathBuf>,
+
+        /// Maximum number of entries to show
+        #[arg(long, default_value = "20")]
+        limit: u32,
+
+        /// Only show failed commit attempts
+        #[arg(long)]
+        failed: bool,
+    },
+
+    /// Re-render a previously recorded --event-log file through the UI
+    Replay {
+        /// Path to the event log written by a previous run
+        path: std::path::PathBuf,
+
+        /// Format to render the replay in
+        #[arg(long, value_enum, default_value = "pretty")]
+        format: artifact::EventLogFormat,
+    },
+
+    /// Commit across many repositories in one invocation
+    Batch {
+        /// A glob pattern matching repo directories, or a file listing one repo path per line
+        #[arg(long)]
+        repos: String,
+
+        /// Maximum number of repos to commit concurrently
+        #[arg(long, default_value = "4")]
+        jobs: usize,
+    },
+
+    /// Run an HMAC-authenticated HTTP daemon that triggers commits remotely
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        listen: String,
+
+        /// File listing pre-shared keys (one per line) accepted for HMAC auth
+        #[arg(long)]
+        psk_file: std::path::PathBuf,
+
+        /// TOML file mapping GitHub "owner/repo" full names to local repo paths
+        #[arg(long)]
+        repo_map: std::path::PathBuf,
+    },
 }
 
 const COMMIT_ACTOR_MANIFEST: &str =
@@ -54,7 +139,7 @@ const COMMIT_ACTOR_MANIFEST: &str =
 
 /// Structured response from the commit actor
 #[derive(Debug, Serialize, Deserialize)]
-struct CommitResult {
+pub(crate) struct CommitResult {
     success: bool,
     message: Option<String>,
     commit_hash: Option<String>,
@@ -70,18 +155,29 @@ struct CommitResult {
 /// Event-driven Theater client that handles all messages asynchronously
 struct EventDrivenClient {
     connection: TheaterConnection,
+    event_log: Option<artifact::EventLog>,
 }
 
 impl EventDrivenClient {
-    async fn new(server_addr: &str) -> Result<Self> {
+    async fn new(server_addr: &str, event_log: Option<artifact::EventLog>) -> Result<Self> {
         let addr: SocketAddr = server_addr.parse()
             .context("Invalid server address")?;
-        
+
         let mut connection = TheaterConnection::new(addr);
         connection.connect().await
             .context("Failed to connect to Theater server")?;
-        
-        Ok(Self { connection })
+
+        Ok(Self { connection, event_log })
+    }
+
+    /// Append an event to the event log, if one is configured, logging
+    /// (not propagating) a write failure so a broken log never aborts a run.
+    fn log_event(&mut self, event: &ChainEvent) {
+        if let Some(log) = &mut self.event_log {
+            if let Err(e) = log.append(event) {
+                eprintln!("Warning: Failed to write event log entry: {}", e);
+            }
+        }
     }
 
     /// Send a command to the server
@@ -112,6 +208,7 @@ impl EventDrivenClient {
                     return Ok(id);
                 },
                 ManagementResponse::ActorEvent { event } => {
+                    self.log_event(&event);
                     handle_commit_event(&event);
                 },
                 ManagementResponse::Error { error } => {
@@ -142,6 +239,7 @@ impl EventDrivenClient {
                     return Ok(message);
                 },
                 ManagementResponse::ActorEvent { event } => {
+                    self.log_event(&event);
                     handle_commit_event(&event);
                 },
                 ManagementResponse::Error { error } => {
@@ -171,6 +269,59 @@ impl EventDrivenClient {
     }
 }
 
+/// Start the commit actor and subscribe to its event stream. Shared by the
+/// single-repo path, `batch::run_repo`, and `serve::dispatch_commit`.
+pub(crate) async fn start_commit_actor(
+    client: &mut EventDrivenClient,
+    initial_state: serde_json::Value,
+) -> Result<TheaterId> {
+    let actor_id = client
+        .start_actor(COMMIT_ACTOR_MANIFEST, initial_state)
+        .await
+        .context("Failed to start commit actor")?;
+
+    client
+        .subscribe_to_events(&actor_id)
+        .await
+        .context("Failed to subscribe to events")?;
+
+    Ok(actor_id)
+}
+
+/// Send one commit request to an already-started actor and parse its
+/// response, bounded by `timeout_seconds`. Shared by the single-repo path,
+/// `batch::run_repo`, and `serve::dispatch_commit`.
+pub(crate) async fn request_commit_result(
+    client: &mut EventDrivenClient,
+    actor_id: &TheaterId,
+    commit_request: serde_json::Value,
+    timeout_seconds: u64,
+) -> Result<CommitResult> {
+    let operation = async {
+        let response_bytes = client
+            .request_actor_message(actor_id, commit_request)
+            .await?;
+        let result: CommitResult = serde_json::from_slice(&response_bytes)
+            .context("Failed to parse commit result")?;
+        Ok::<CommitResult, anyhow::Error>(result)
+    };
+
+    timeout(Duration::from_secs(timeout_seconds), operation)
+        .await
+        .context("Commit operation timed out")?
+        .context("Commit operation failed")
+}
+
+/// Stop an actor, logging (not propagating) a failure to stop it — a
+/// dead/unreachable actor shouldn't turn an otherwise-successful commit
+/// into an error. Shared by the single-repo path, `batch::run_repo`, and
+/// `serve::dispatch_commit`.
+pub(crate) async fn stop_commit_actor(client: &mut EventDrivenClient, actor_id: &TheaterId) {
+    if let Err(e) = client.stop_actor(actor_id).await {
+        eprintln!("Warning: Failed to stop actor: {}", e);
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -180,11 +331,30 @@ async fn main() -> Result<()> {
         tracing_subscriber::fmt::init();
     }
 
-    // Validate prerequisites
-    validate_prerequisites()?;
+    match &args.command {
+        Some(Commands::Log { repo, limit, failed }) => {
+            return cmd_log(repo.as_deref(), *limit, *failed);
+        }
+        Some(Commands::Replay { path, format }) => {
+            return artifact::replay(path, *format);
+        }
+        Some(Commands::Batch { repos, jobs }) => {
+            return batch::run(&args, repos, *jobs).await;
+        }
+        Some(Commands::Serve {
+            listen,
+            psk_file,
+            repo_map,
+        }) => {
+            return serve::run(&args, listen, psk_file, repo_map).await;
+        }
+        None => {}
+    }
 
     // Get repository information
     let repo_path = env::current_dir().context("Failed to get current directory")?;
+    validate_prerequisites(&repo_path)?;
+
     let api_key = env::var("GOOGLE_GEMINI_API_KEY")
         .context("GOOGLE_GEMINI_API_KEY environment variable not set")?;
 
@@ -202,10 +372,65 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn validate_prerequisites() -> Result<()> {
+/// Resolve a `--repo` argument to the same form `repo_path` is recorded in,
+/// so `th-commit log --repo .` (or any relative/trailing-slash path)
+/// matches history written from an absolute `env::current_dir()`.
+fn resolve_repo_filter(repo: Option<&std::path::Path>) -> Result<Option<String>> {
+    repo.map(|p| {
+        std::fs::canonicalize(p)
+            .with_context(|| format!("Failed to resolve --repo {}", p.display()))
+            .map(|p| p.to_string_lossy().to_string())
+    })
+    .transpose()
+}
+
+/// Render the commit journal for the `th-commit log` subcommand.
+fn cmd_log(repo: Option<&std::path::Path>, limit: u32, failed: bool) -> Result<()> {
+    let journal = state::Journal::open_default().context("Failed to open commit journal")?;
+    let repo_filter = resolve_repo_filter(repo)?;
+    let records = journal
+        .history(repo_filter.as_deref(), limit, failed)
+        .context("Failed to read commit journal")?;
+
+    ui::print_header();
+    ui::print_section("Commit History");
+
+    if records.is_empty() {
+        println!("  No commit history recorded yet.");
+        return Ok(());
+    }
+
+    for record in &records {
+        println!();
+        ui::print_item("Timestamp", &record.timestamp, Some("dim"));
+        ui::print_item("Repository", &record.repo_path, Some("highlight"));
+        ui::print_item(
+            "Status",
+            if record.success { "success" } else { "failed" },
+            Some(if record.success { "success" } else { "error" }),
+        );
+
+        if let Some(hash) = &record.commit_hash {
+            ui::print_item("Commit hash", hash, Some("info"));
+        }
+        if let Some(message) = &record.commit_message {
+            ui::print_commit_message(message);
+        }
+        if let Some(error) = &record.error {
+            ui::print_item("Error", error, Some("error"));
+        }
+        if record.pushed {
+            println!("  🌐 Pushed to remote");
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_prerequisites(repo_path: &std::path::Path) -> Result<()> {
     // Check if we're in a git repository
-    if !std::path::Path::new(".git").exists() {
-        return Err(anyhow!("Not in a git repository"));
+    if !repo_path.join(".git").exists() {
+        return Err(anyhow!("{} is not a git repository", repo_path.display()));
     }
 
     // Check if git is available
@@ -221,70 +446,151 @@ fn validate_prerequisites() -> Result<()> {
 }
 
 async fn execute_commit(args: &Args, repo_path: std::path::PathBuf, api_key: String) -> Result<()> {
+    // Open the event log before connecting so a long run's very first
+    // event is captured
+    let event_log = args
+        .event_log
+        .as_deref()
+        .map(artifact::EventLog::open)
+        .transpose()
+        .context("Failed to open event log")?;
+
     // Create event-driven client
-    let mut client = EventDrivenClient::new(&args.server).await
+    let mut client = EventDrivenClient::new(&args.server, event_log).await
         .context("Failed to connect to Theater server")?;
 
     info!("Connected to Theater server at {}", args.server);
 
+    // Load the optional Lua hook script up front so pre_commit can shape
+    // the actor's initial state
+    let script = args
+        .script
+        .as_deref()
+        .map(lua::Script::load)
+        .transpose()
+        .context("Failed to load Lua script")?;
+
+    let mut message_prefix = args.prefix.clone();
+    if let Some(script) = &script {
+        let pre_state = json!({
+            "repository_path": repo_path.to_string_lossy(),
+            "message_prefix": message_prefix,
+            "skip_staging": args.skip_staging,
+        });
+        let outcome = script
+            .run_pre_commit(&pre_state)
+            .context("pre_commit hook failed")?;
+        if outcome.abort {
+            return Err(anyhow!("Commit aborted by pre_commit hook"));
+        }
+        if outcome.message_prefix.is_some() {
+            message_prefix = outcome.message_prefix;
+        }
+    }
+
     // Prepare initial state for commit actor
     let initial_state = json!({
         "repository_path": repo_path.to_string_lossy(),
         "api_key": api_key,
         "auto_push": args.auto_push,
-        "message_prefix": args.prefix,
+        "message_prefix": message_prefix,
         "skip_staging": args.skip_staging,
         "dry_run": args.dry_run
     });
 
-    println!("üöÄ Starting commit actor...");
+    println!("🚀 Starting commit actor...");
 
     // Start commit actor (this handles all the async message processing)
-    let actor_id = client
-        .start_actor(COMMIT_ACTOR_MANIFEST, initial_state)
-        .await
-        .context("Failed to start commit actor")?;
+    let actor_id = start_commit_actor(&mut client, initial_state).await?;
 
     ui::print_item("Actor ID", &actor_id.to_string(), Some("info"));
 
-    // Subscribe to events (events will be handled automatically during request processing)
-    client.subscribe_to_events(&actor_id).await
-        .context("Failed to subscribe to events")?;
-
-    // Send commit request
-    let commit_request = json!({
-        "action": "commit",
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    });
-
-    println!("üìù Requesting commit...");
-
-    // Use timeout for the entire operation
-    let operation = async {
-        let response_bytes = client
-            .request_actor_message(&actor_id, commit_request)
-            .await?;
-        
-        // Parse response
-        let result: CommitResult = serde_json::from_slice(&response_bytes)
-            .context("Failed to parse commit result")?;
-
-        Ok::<CommitResult, anyhow::Error>(result)
-    };
+    // Send the commit request, re-requesting a regenerate if the
+    // post_commit hook rejects the generated message (capped to avoid an
+    // unbounded loop if a script always rejects)
+    const MAX_POST_COMMIT_ATTEMPTS: u32 = 3;
+    let mut result = None;
+    for attempt in 1..=MAX_POST_COMMIT_ATTEMPTS {
+        let commit_request = json!({
+            "action": "commit",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "attempt": attempt
+        });
+
+        println!("📝 Requesting commit...");
+
+        let mut attempt_result =
+            request_commit_result(&mut client, &actor_id, commit_request, args.timeout_seconds)
+                .await?;
+
+        if let Some(script) = &script {
+            match script
+                .run_post_commit(&attempt_result)
+                .context("post_commit hook failed")?
+            {
+                lua::PostCommitOutcome::Accept => {}
+                lua::PostCommitOutcome::Rewrite(message) => {
+                    attempt_result.commit_message = Some(message);
+                }
+                lua::PostCommitOutcome::Regenerate => {
+                    if attempt < MAX_POST_COMMIT_ATTEMPTS {
+                        println!("🔁 post_commit hook rejected the message, requesting regenerate...");
+                        continue;
+                    }
+                    eprintln!(
+                        "Warning: post_commit hook kept rejecting the message after {} attempts, using it anyway",
+                        attempt
+                    );
+                }
+            }
+        }
 
-    let result = timeout(Duration::from_secs(args.timeout_seconds), operation)
-        .await
-        .context("Commit operation timed out")?
-        .context("Commit operation failed")?;
+        result = Some(attempt_result);
+        break;
+    }
+    let result = result.context("No commit attempt completed")?;
 
     // Display results
-    display_commit_result(&result)?;
+    let notify_config = args
+        .notify_config
+        .as_deref()
+        .map(notifier::NotifyConfig::load)
+        .transpose()
+        .context("Failed to load notify config")?;
+    display_commit_result(&result, &repo_path.to_string_lossy(), notify_config.as_ref()).await?;
+
+    // Shape the journal entry from the commit result before we touch the
+    // database, so a later journal-write failure can't take the mail step
+    // down with it.
+    let record = build_commit_record(&repo_path, &actor_id, &result);
+
+    // Record this operation in the local commit journal (non-fatal on failure)
+    if let Err(e) = persist_commit_record(&record) {
+        eprintln!("Warning: Failed to record commit journal entry: {}", e);
+    }
 
-    // Clean shutdown
-    if let Err(e) = client.stop_actor(&actor_id).await {
-        eprintln!("Warning: Failed to stop actor: {}", e);
+    // Email the commit as a patch to reviewers, if requested
+    if let Some(mail_to) = &args.mail_to {
+        if record.success {
+            if let (Some(hash), Some(message)) = (&record.commit_hash, &record.commit_message) {
+                let config = mailer::MailConfig::new(
+                    mail_to.clone(),
+                    args.mail_from
+                        .clone()
+                        .unwrap_or_else(|| "th-commit@localhost".to_string()),
+                );
+                if let Err(e) = mailer::send_patch_mail(&repo_path, hash, message, &config) {
+                    eprintln!("Warning: Failed to email commit patch: {}", e);
+                }
+            } else {
+                eprintln!("Warning: Commit succeeded but no hash/message available to mail");
+            }
+        }
     }
 
+    // Clean shutdown
+    stop_commit_actor(&mut client, &actor_id).await;
+
     Ok(())
 }
 
@@ -319,7 +625,11 @@ fn handle_commit_event(event: &ChainEvent) {
     }
 }
 
-fn display_commit_result(result: &CommitResult) -> Result<()> {
+async fn display_commit_result(
+    result: &CommitResult,
+    repo_path: &str,
+    notify_config: Option<&notifier::NotifyConfig>,
+) -> Result<()> {
     println!("\n{}", "=".repeat(50));
 
     // Handle pipe-delimited status format if present
@@ -331,9 +641,97 @@ fn display_commit_result(result: &CommitResult) -> Result<()> {
     }
 
     println!("{}", "=".repeat(50));
+
+    // Fan the same structured result out to any configured notifiers
+    if let Some(config) = notify_config {
+        notifier::notify_all(config, repo_path, result).await;
+    }
+
     Ok(())
 }
 
+/// Build a journal entry from a `CommitResult`, handling both the
+/// pipe-delimited `status_msg` format and the regular JSON fields.
+///
+/// This is infallible by design: it only shapes data already in hand, so
+/// callers can use the result (e.g. to email a patch) even when persisting
+/// it to the journal afterwards fails.
+fn build_commit_record(
+    repo_path: &std::path::Path,
+    actor_id: &TheaterId,
+    result: &CommitResult,
+) -> state::CommitRecord {
+    if let Some(status_msg) = &result.status_msg {
+        let mut success = false;
+        let mut error = None;
+        let mut hash = None;
+        let mut commit_msg = None;
+        let mut files = None;
+        let mut ins = None;
+        let mut dels = None;
+
+        for field in status_msg.split('|') {
+            if let Some((key, value)) = field.split_once(':') {
+                match key {
+                    "STATUS" => success = value == "true",
+                    "MESSAGE" if value != "none" => error = Some(value.to_string()),
+                    "HASH" if value != "none" => hash = Some(value.to_string()),
+                    "COMMIT_MSG" if value != "none" => commit_msg = Some(value.to_string()),
+                    "FILES" => files = value.parse().ok(),
+                    "INS" => ins = value.parse().ok(),
+                    "DELS" => dels = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+
+        state::CommitRecord {
+            actor_id: actor_id.to_string(),
+            repo_path: repo_path.to_string_lossy().to_string(),
+            commit_hash: hash,
+            commit_message: commit_msg,
+            files_changed: files,
+            insertions: ins,
+            deletions: dels,
+            pushed: false,
+            success,
+            error,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    } else {
+        state::CommitRecord {
+            actor_id: actor_id.to_string(),
+            repo_path: repo_path.to_string_lossy().to_string(),
+            commit_hash: result.commit_hash.clone(),
+            commit_message: result.commit_message.clone(),
+            files_changed: result.files_changed,
+            insertions: result.insertions,
+            deletions: result.deletions,
+            pushed: result.pushed.unwrap_or(false),
+            success: result.success,
+            error: result.error.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Persist a journal entry to the local commit journal.
+fn persist_commit_record(record: &state::CommitRecord) -> Result<()> {
+    let journal = state::Journal::open_default()?;
+    journal.record(record)
+}
+
+/// Build a journal entry from a `CommitResult` and persist it.
+fn journal_result(
+    repo_path: &std::path::Path,
+    actor_id: &TheaterId,
+    result: &CommitResult,
+) -> Result<state::CommitRecord> {
+    let record = build_commit_record(repo_path, actor_id, result);
+    persist_commit_record(&record)?;
+    Ok(record)
+}
+
 fn parse_and_display_status_msg(status_msg: &str) -> Result<()> {
     let mut success = false;
     let mut message = None;
@@ -449,3 +847,47 @@ fn display_json_result(result: &CommitResult) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_repo_filter_passes_through_none() {
+        assert_eq!(resolve_repo_filter(None).unwrap(), None);
+    }
+
+    #[test]
+    fn resolve_repo_filter_canonicalizes_dot_component_and_trailing_slash_paths() {
+        let dir = std::env::temp_dir().join(format!(
+            "th-commit-test-resolve-repo-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let canonical = std::fs::canonicalize(&dir).unwrap().to_string_lossy().to_string();
+
+        // A trailing-slash path and a path with a redundant "." component
+        // both resolve the same as the bare canonical path, mirroring the
+        // relative paths `th-commit log --repo .` passes in practice.
+        let with_trailing_slash = dir.join("");
+        let with_dot_component = dir.join(".");
+
+        assert_eq!(
+            resolve_repo_filter(Some(&with_trailing_slash)).unwrap(),
+            Some(canonical.clone())
+        );
+        assert_eq!(
+            resolve_repo_filter(Some(&with_dot_component)).unwrap(),
+            Some(canonical)
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_repo_filter_errors_on_missing_path() {
+        let missing = std::env::temp_dir().join("th-commit-test-does-not-exist-xyz");
+        assert!(resolve_repo_filter(Some(&missing)).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Commit-completion notifier backends.
+//!
+//! After a commit succeeds or fails, the result is fanned out to every
+//! backend listed in the `--notify-config` TOML file. Backends are
+//! independent: a delivery failure on one is logged as a warning and
+//! never aborts the commit operation or blocks the other backends.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::CommitResult;
+
+/// How long a single backend is allowed to hang before it's treated as a
+/// non-fatal failure, same as a rejected webhook or a refused IRC connection.
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Parsed `--notify-config` TOML file.
+#[derive(Debug, Deserialize)]
+pub struct NotifyConfig {
+    #[serde(default, rename = "backend")]
+    pub backends: Vec<BackendConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackendConfig {
+    /// HTTP POST of the commit result as JSON.
+    Webhook { url: String },
+    /// Post a one-line summary to an IRC channel.
+    Irc {
+        server: String,
+        channel: String,
+        #[serde(default = "default_irc_nick")]
+        nick: String,
+    },
+    /// Print the summary to stdout. Runs only when listed in the config
+    /// file; without `--notify-config` no backend runs.
+    Stdout,
+}
+
+fn default_irc_nick() -> String {
+    "th-commit".to_string()
+}
+
+impl NotifyConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read notify config at {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse notify config at {}", path.display()))
+    }
+}
+
+/// A JSON-serializable snapshot of a commit result, sent to webhook backends.
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    repo_path: &'a str,
+    result: &'a CommitResult,
+}
+
+/// Dispatch a commit result to every configured backend. Failures are
+/// logged as warnings and do not propagate.
+pub async fn notify_all(config: &NotifyConfig, repo_path: &str, result: &CommitResult) {
+    for backend in &config.backends {
+        if let Err(e) = notify_one(backend, repo_path, result).await {
+            eprintln!("Warning: notifier backend failed: {}", e);
+        }
+    }
+}
+
+async fn notify_one(backend: &BackendConfig, repo_path: &str, result: &CommitResult) -> Result<()> {
+    match backend {
+        BackendConfig::Webhook { url } => send_webhook(url, repo_path, result).await,
+        BackendConfig::Irc {
+            server,
+            channel,
+            nick,
+        } => send_irc(server, channel, nick, repo_path, result).await,
+        BackendConfig::Stdout => {
+            send_stdout(repo_path, result);
+            Ok(())
+        }
+    }
+}
+
+async fn send_webhook(url: &str, repo_path: &str, result: &CommitResult) -> Result<()> {
+    let payload = WebhookPayload { repo_path, result };
+    let client = reqwest::Client::builder()
+        .connect_timeout(BACKEND_TIMEOUT)
+        .timeout(BACKEND_TIMEOUT)
+        .build()
+        .context("Failed to build webhook HTTP client")?;
+    let response = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .with_context(|| format!("Failed to POST webhook to {}", url))?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Webhook {} returned status {}",
+            url,
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn send_irc(server: &str, channel: &str, nick: &str, repo_path: &str, result: &CommitResult) -> Result<()> {
+    let summary = summarize(repo_path, result);
+    let line = irc_safe(&summary);
+
+    tokio::time::timeout(BACKEND_TIMEOUT, send_irc_lines(server, channel, nick, &line))
+        .await
+        .with_context(|| format!("Timed out talking to IRC server {}", server))?
+}
+
+/// Collapse a summary onto a single IRC line. `commit_message` (and thus
+/// `summary`) is routinely multi-line (subject + blank line + body), but
+/// `PRIVMSG` only accepts one line per message, so embedding it raw would
+/// inject extra lines the server tries to parse as protocol input.
+fn irc_safe(summary: &str) -> String {
+    summary.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
+
+async fn send_irc_lines(server: &str, channel: &str, nick: &str, summary: &str) -> Result<()> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .with_context(|| format!("Failed to connect to IRC server {}", server))?;
+
+    stream
+        .write_all(format!("NICK {}\r\n", nick).as_bytes())
+        .await?;
+    stream
+        .write_all(format!("USER {} 0 * :th-commit notifier\r\n", nick).as_bytes())
+        .await?;
+    stream
+        .write_all(format!("JOIN {}\r\n", channel).as_bytes())
+        .await?;
+    stream
+        .write_all(format!("PRIVMSG {} :{}\r\n", channel, summary).as_bytes())
+        .await?;
+    stream.write_all(b"QUIT\r\n").await?;
+    stream.flush().await?;
+
+    Ok(())
+}
+
+fn send_stdout(repo_path: &str, result: &CommitResult) {
+    println!("{}", summarize(repo_path, result));
+}
+
+fn summarize(repo_path: &str, result: &CommitResult) -> String {
+    if result.success {
+        let hash = result.commit_hash.as_deref().unwrap_or("unknown");
+        let message = result.commit_message.as_deref().unwrap_or("(no message)");
+        format!("committed {} to {}: {}", hash, repo_path, message)
+    } else {
+        let error = result.error.as_deref().unwrap_or("unknown error");
+        format!("commit failed in {}: {}", repo_path, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(json: serde_json::Value) -> CommitResult {
+        serde_json::from_value(json).expect("valid CommitResult fixture")
+    }
+
+    #[test]
+    fn summarize_success_includes_hash_and_message() {
+        let result = result(serde_json::json!({
+            "success": true,
+            "commit_hash": "abc1234",
+            "commit_message": "fix: thing"
+        }));
+        assert_eq!(
+            summarize("repo", &result),
+            "committed abc1234 to repo: fix: thing"
+        );
+    }
+
+    #[test]
+    fn summarize_success_falls_back_when_fields_missing() {
+        let result = result(serde_json::json!({ "success": true }));
+        assert_eq!(summarize("repo", &result), "committed unknown to repo: (no message)");
+    }
+
+    #[test]
+    fn summarize_failure_includes_error() {
+        let result = result(serde_json::json!({
+            "success": false,
+            "error": "nothing to commit"
+        }));
+        assert_eq!(
+            summarize("repo", &result),
+            "commit failed in repo: nothing to commit"
+        );
+    }
+
+    #[test]
+    fn summarize_failure_falls_back_when_error_missing() {
+        let result = result(serde_json::json!({ "success": false }));
+        assert_eq!(summarize("repo", &result), "commit failed in repo: unknown error");
+    }
+
+    #[test]
+    fn irc_safe_collapses_multiline_commit_messages() {
+        let result = result(serde_json::json!({
+            "success": true,
+            "commit_hash": "abc1234",
+            "commit_message": "fix: thing\n\nLonger body\r\nwith a second line"
+        }));
+        let summary = summarize("repo", &result);
+        let line = irc_safe(&summary);
+        assert!(!line.contains('\n'));
+        assert!(!line.contains('\r'));
+        assert_eq!(
+            line,
+            "committed abc1234 to repo: fix: thing  Longer body with a second line"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! HTTP daemon that triggers commits remotely.
+//!
+//! `th-commit serve` authenticates each request the way build-o-tron
+//! verifies GitHub webhooks: every POST body is checked against
+//! `HMAC-SHA256(body, psk)` for one of a set of configured pre-shared
+//! keys, compared in constant time against the hex digest in the
+//! `X-Hub-Signature-256: sha256=...` header. A valid request's JSON body
+//! is parsed as a GitHub push payload to pick which local repo/branch to
+//! commit, then the usual actor/`EventDrivenClient` path runs the commit
+//! and the resulting `CommitResult` is returned as the HTTP response.
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::info;
+
+use crate::{
+    journal_result, request_commit_result, start_commit_actor, stop_commit_actor, ui,
+    validate_prerequisites, Args, CommitResult, EventDrivenClient,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// One entry of the `--repo-map` TOML file: which local repo a GitHub
+/// `repository.full_name` corresponds to.
+#[derive(Debug, Deserialize)]
+struct RepoMapEntry {
+    path: PathBuf,
+    #[serde(default)]
+    branch: Option<String>,
+}
+
+/// The minimal shape of a GitHub push webhook payload we care about.
+#[derive(Debug, Deserialize)]
+struct PushPayload {
+    after: String,
+    repository: PushRepository,
+    #[serde(rename = "ref", default)]
+    git_ref: Option<String>,
+    #[serde(default)]
+    head_commit: Option<PushHeadCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushHeadCommit {
+    id: String,
+    message: String,
+}
+
+struct ServeState {
+    psks: Vec<String>,
+    repo_map: HashMap<String, RepoMapEntry>,
+    args: Args,
+}
+
+/// Start the webhook server and block until it exits.
+pub async fn run(args: &Args, listen: &str, psk_file: &Path, repo_map_file: &Path) -> Result<()> {
+    let psks = load_psks(psk_file)?;
+    let repo_map = load_repo_map(repo_map_file)?;
+
+    if psks.is_empty() {
+        return Err(anyhow::anyhow!("No pre-shared keys configured in {}", psk_file.display()));
+    }
+
+    let state = Arc::new(ServeState {
+        psks,
+        repo_map,
+        args: args.clone(),
+    });
+
+    let app = Router::new()
+        .route("/webhook", post(handle_webhook))
+        .with_state(state);
+
+    let addr: SocketAddr = listen.parse().context("Invalid --listen address")?;
+
+    ui::print_header();
+    ui::print_item("Listening", listen, Some("highlight"));
+    ui::print_item("Repositories configured", &app_repo_count(repo_map_file)?.to_string(), Some("info"));
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind {}", listen))?;
+
+    axum::serve(listener, app).await.context("Server error")?;
+
+    Ok(())
+}
+
+fn app_repo_count(repo_map_file: &Path) -> Result<usize> {
+    Ok(load_repo_map(repo_map_file)?.len())
+}
+
+async fn handle_webhook(
+    State(state): State<Arc<ServeState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if let Err(e) = verify_signature(&state.psks, &headers, &body) {
+        eprintln!("Warning: rejected webhook request: {}", e);
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": e.to_string() })),
+        );
+    }
+
+    match dispatch_commit(&state, &body).await {
+        Ok(result) => (
+            StatusCode::OK,
+            Json(serde_json::to_value(result).unwrap_or_else(|_| json!({}))),
+        ),
+        Err(e) => {
+            eprintln!("Warning: webhook-triggered commit failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+        }
+    }
+}
+
+/// Verify `X-Hub-Signature-256` against `HMAC-SHA256(body, psk)` for each
+/// configured pre-shared key, accepting on the first match.
+fn verify_signature(psks: &[String], headers: &HeaderMap, body: &[u8]) -> Result<()> {
+    let header_value = headers
+        .get("X-Hub-Signature-256")
+        .context("Missing X-Hub-Signature-256 header")?
+        .to_str()
+        .context("X-Hub-Signature-256 header is not valid UTF-8")?;
+
+    let provided_hex = header_value
+        .strip_prefix("sha256=")
+        .context("X-Hub-Signature-256 header is missing the sha256= prefix")?;
+    let provided = hex::decode(provided_hex).context("X-Hub-Signature-256 is not valid hex")?;
+
+    for psk in psks {
+        let mut mac = HmacSha256::new_from_slice(psk.as_bytes())
+            .expect("HMAC-SHA256 accepts a key of any length");
+        mac.update(body);
+        let expected = mac.finalize().into_bytes();
+        if expected.as_slice().ct_eq(&provided).into() {
+            return Ok(());
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "Signature did not match any configured pre-shared key"
+    ))
+}
+
+/// Parse the push payload, resolve it to a local repo, and run the commit.
+async fn dispatch_commit(state: &ServeState, body: &[u8]) -> Result<CommitResult> {
+    let payload: PushPayload =
+        serde_json::from_slice(body).context("Failed to parse push payload")?;
+
+    let entry = state
+        .repo_map
+        .get(&payload.repository.full_name)
+        .with_context(|| {
+            format!(
+                "No repo mapping configured for {}",
+                payload.repository.full_name
+            )
+        })?;
+
+    if let (Some(expected_branch), Some(git_ref)) = (&entry.branch, &payload.git_ref) {
+        let branch = git_ref.trim_start_matches("refs/heads/");
+        if branch != expected_branch {
+            return Err(anyhow::anyhow!(
+                "Ignoring push to {} (expected branch {})",
+                branch,
+                expected_branch
+            ));
+        }
+    }
+
+    info!(
+        "Triggering commit for {} at {} (head: {})",
+        payload.repository.full_name,
+        payload.after,
+        payload
+            .head_commit
+            .as_ref()
+            .map(|c| c.message.lines().next().unwrap_or(&c.id))
+            .unwrap_or("<no head_commit in payload>")
+    );
+
+    validate_prerequisites(&entry.path)?;
+
+    let api_key = std::env::var("GOOGLE_GEMINI_API_KEY")
+        .context("GOOGLE_GEMINI_API_KEY environment variable not set")?;
+
+    let mut client = EventDrivenClient::new(&state.args.server, None)
+        .await
+        .context("Failed to connect to Theater server")?;
+
+    let initial_state = json!({
+        "repository_path": entry.path.to_string_lossy(),
+        "api_key": api_key,
+        "auto_push": state.args.auto_push,
+        "message_prefix": state.args.prefix,
+        "skip_staging": state.args.skip_staging,
+        "dry_run": state.args.dry_run
+    });
+
+    let actor_id = start_commit_actor(&mut client, initial_state).await?;
+
+    let commit_request = json!({
+        "action": "commit",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "trigger_commit": payload.after,
+    });
+
+    let result = request_commit_result(
+        &mut client,
+        &actor_id,
+        commit_request,
+        state.args.timeout_seconds,
+    )
+    .await?;
+
+    stop_commit_actor(&mut client, &actor_id).await;
+
+    if let Err(e) = journal_result(&entry.path, &actor_id, &result) {
+        eprintln!("Warning: Failed to record commit journal entry: {}", e);
+    }
+
+    Ok(result)
+}
+
+fn load_psks(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read psk file {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn load_repo_map(path: &Path) -> Result<HashMap<String, RepoMapEntry>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read repo map {}", path.display()))?;
+    toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse repo map {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_headers(psk: &str, body: &[u8]) -> HeaderMap {
+        let mut mac =
+            HmacSha256::new_from_slice(psk.as_bytes()).expect("key of any length is valid");
+        mac.update(body);
+        let digest = hex::encode(mac.finalize().into_bytes());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Hub-Signature-256",
+            format!("sha256={}", digest).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn accepts_signature_matching_a_configured_psk() {
+        let body = b"push payload";
+        let headers = signed_headers("correct-horse", body);
+        let psks = vec!["wrong-key".to_string(), "correct-horse".to_string()];
+
+        assert!(verify_signature(&psks, &headers, body).is_ok());
+    }
+
+    #[test]
+    fn rejects_signature_matching_no_configured_psk() {
+        let body = b"push payload";
+        let headers = signed_headers("correct-horse", body);
+        let psks = vec!["wrong-key".to_string()];
+
+        assert!(verify_signature(&psks, &headers, body).is_err());
+    }
+
+    #[test]
+    fn rejects_signature_for_a_tampered_body() {
+        let body = b"push payload";
+        let headers = signed_headers("correct-horse", body);
+        let psks = vec!["correct-horse".to_string()];
+
+        assert!(verify_signature(&psks, &headers, b"different payload").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_signature_header() {
+        let headers = HeaderMap::new();
+        let psks = vec!["correct-horse".to_string()];
+
+        assert!(verify_signature(&psks, &headers, b"body").is_err());
+    }
+
+    #[test]
+    fn rejects_header_without_sha256_prefix() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Hub-Signature-256", "deadbeef".parse().unwrap());
+        let psks = vec!["correct-horse".to_string()];
+
+        assert!(verify_signature(&psks, &headers, b"body").is_err());
+    }
+
+    #[test]
+    fn rejects_non_hex_signature() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Hub-Signature-256",
+            "sha256=not-hex-at-all".parse().unwrap(),
+        );
+        let psks = vec!["correct-horse".to_string()];
+
+        assert!(verify_signature(&psks, &headers, b"body").is_err());
+    }
+}
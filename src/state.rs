@@ -0,0 +1,267 @@
+//! Persistent commit journal backed by a local SQLite database.
+//!
+//! Every commit operation `th-commit` performs gets a row here, so users
+//! can audit AI-generated commits across all their repositories with
+//! `th-commit log` instead of grepping git reflog one repo at a time.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+/// One row of the commit journal.
+#[derive(Debug)]
+pub struct CommitRecord {
+    pub actor_id: String,
+    pub repo_path: String,
+    pub commit_hash: Option<String>,
+    pub commit_message: Option<String>,
+    pub files_changed: Option<u64>,
+    pub insertions: Option<u64>,
+    pub deletions: Option<u64>,
+    pub pushed: bool,
+    pub success: bool,
+    pub error: Option<String>,
+    pub timestamp: String,
+}
+
+/// A connection to the local `th-commit` state database.
+pub struct Journal {
+    conn: Connection,
+}
+
+impl Journal {
+    /// Open (creating if needed) the journal at the default location,
+    /// `~/.th-commit/state.db`.
+    pub fn open_default() -> Result<Self> {
+        Self::open(&default_db_path()?)
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open state database at {}", path.display()))?;
+
+        // Concurrent batch runs can write near-simultaneously; wait for the
+        // lock instead of failing immediately with SQLITE_BUSY.
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .context("Failed to set busy timeout on state database")?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS commits (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                actor_id        TEXT NOT NULL,
+                repo_path       TEXT NOT NULL,
+                commit_hash     TEXT,
+                commit_message  TEXT,
+                files_changed   INTEGER,
+                insertions      INTEGER,
+                deletions       INTEGER,
+                pushed          INTEGER NOT NULL,
+                success         INTEGER NOT NULL,
+                error           TEXT,
+                timestamp       TEXT NOT NULL
+            );",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    /// Record the outcome of a single commit operation.
+    pub fn record(&self, record: &CommitRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO commits (
+                actor_id, repo_path, commit_hash, commit_message,
+                files_changed, insertions, deletions, pushed, success, error, timestamp
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                record.actor_id,
+                record.repo_path,
+                record.commit_hash,
+                record.commit_message,
+                record.files_changed,
+                record.insertions,
+                record.deletions,
+                record.pushed as i64,
+                record.success as i64,
+                record.error,
+                record.timestamp,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Fetch journal history, most recent first.
+    ///
+    /// `repo` filters to an exact repo path match; `failed_only` restricts
+    /// to rows where the commit did not succeed.
+    pub fn history(
+        &self,
+        repo: Option<&str>,
+        limit: u32,
+        failed_only: bool,
+    ) -> Result<Vec<CommitRecord>> {
+        let mut sql = String::from(
+            "SELECT actor_id, repo_path, commit_hash, commit_message,
+                    files_changed, insertions, deletions, pushed, success, error, timestamp
+             FROM commits",
+        );
+
+        let mut clauses = Vec::new();
+        let mut bound_params = 0u32;
+        if repo.is_some() {
+            bound_params += 1;
+            clauses.push("repo_path = ?1".to_string());
+        }
+        if failed_only {
+            clauses.push("success = 0".to_string());
+        }
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY id DESC LIMIT ?");
+        sql.push_str(&(bound_params + 1).to_string());
+
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let map_row = |row: &rusqlite::Row| -> rusqlite::Result<CommitRecord> {
+            Ok(CommitRecord {
+                actor_id: row.get(0)?,
+                repo_path: row.get(1)?,
+                commit_hash: row.get(2)?,
+                commit_message: row.get(3)?,
+                files_changed: row.get(4)?,
+                insertions: row.get(5)?,
+                deletions: row.get(6)?,
+                pushed: row.get::<_, i64>(7)? != 0,
+                success: row.get::<_, i64>(8)? != 0,
+                error: row.get(9)?,
+                timestamp: row.get(10)?,
+            })
+        };
+
+        let rows = if let Some(repo) = repo {
+            stmt.query_map(params![repo, limit], map_row)?
+        } else {
+            stmt.query_map(params![limit], map_row)?
+        };
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(records)
+    }
+}
+
+fn default_db_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".th-commit").join("state.db"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_DB_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A journal backed by a throwaway file, removed when the guard drops.
+    struct TempJournal {
+        journal: Journal,
+        path: PathBuf,
+    }
+
+    impl TempJournal {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "th-commit-test-{}-{}.db",
+                std::process::id(),
+                TEST_DB_COUNTER.fetch_add(1, Ordering::SeqCst)
+            ));
+            let journal = Journal::open(&path).expect("failed to open temp journal");
+            Self { journal, path }
+        }
+
+        fn record(&self, repo_path: &str, success: bool) {
+            self.journal
+                .record(&CommitRecord {
+                    actor_id: "actor-1".to_string(),
+                    repo_path: repo_path.to_string(),
+                    commit_hash: Some("deadbeef".to_string()),
+                    commit_message: Some("a commit".to_string()),
+                    files_changed: Some(1),
+                    insertions: Some(1),
+                    deletions: Some(0),
+                    pushed: false,
+                    success,
+                    error: if success {
+                        None
+                    } else {
+                        Some("boom".to_string())
+                    },
+                    timestamp: "2026-01-01T00:00:00Z".to_string(),
+                })
+                .expect("failed to record journal entry");
+        }
+    }
+
+    impl Drop for TempJournal {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn history_respects_limit_with_no_filters() {
+        let db = TempJournal::new();
+        for _ in 0..5 {
+            db.record("/repo/a", true);
+        }
+
+        let records = db.journal.history(None, 3, false).unwrap();
+        assert_eq!(records.len(), 3);
+    }
+
+    #[test]
+    fn history_filters_by_repo_path() {
+        let db = TempJournal::new();
+        db.record("/repo/a", true);
+        db.record("/repo/b", true);
+        db.record("/repo/a", true);
+
+        let records = db.journal.history(Some("/repo/a"), 10, false).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.repo_path == "/repo/a"));
+    }
+
+    #[test]
+    fn history_filters_failed_only_with_no_repo() {
+        let db = TempJournal::new();
+        db.record("/repo/a", true);
+        db.record("/repo/b", false);
+        db.record("/repo/c", false);
+
+        let records = db.journal.history(None, 10, true).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| !r.success));
+    }
+
+    #[test]
+    fn history_filters_failed_only_with_repo_and_limit() {
+        let db = TempJournal::new();
+        db.record("/repo/a", false);
+        db.record("/repo/a", true);
+        db.record("/repo/a", false);
+        db.record("/repo/b", false);
+
+        let records = db.journal.history(Some("/repo/a"), 1, true).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].repo_path, "/repo/a");
+        assert!(!records[0].success);
+    }
+}
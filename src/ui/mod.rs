@@ -110,6 +110,35 @@ pub fn print_commit_message(message: &str) {
     println!("  └{}┘", line);
 }
 
+// Print a simple column-aligned table, e.g. for batch commit summaries
+pub fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let header_line: Vec<String> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| format!("{:width$}", h, width = widths[i]))
+        .collect();
+    println!("  {}", header_line.join("  ").bold());
+
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    println!("  {}", separator.join("  ").dimmed());
+
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{:width$}", cell, width = widths[i]))
+            .collect();
+        println!("  {}", line.join("  "));
+    }
+}
+
 // Print error message
 pub fn print_error(message: &str) {
     eprintln!("{} {}", "Error:".bold().red(), message);